@@ -1,5 +1,6 @@
 //! A button that allows for toggling boolean state.
 
+use std::time::Instant;
 use {
     Color,
     Colorable,
@@ -8,9 +9,15 @@ use {
     Labelable,
     Positionable,
     Scalar,
+    Theme,
     Widget,
 };
+use event;
+use image;
+use input;
+use utils;
 use widget;
+use widget::triangles::Triangle;
 
 
 /// A pressable widget for toggling the state of a bool.
@@ -24,12 +31,28 @@ use widget;
 pub struct Toggle<'a> {
     common: widget::CommonBuilder,
     value: bool,
-    maybe_label: Option<&'a str>,
+    maybe_content: Option<Content<'a>>,
     style: Style,
+    is_switch: bool,
+    flat: bool,
     /// If true, will allow user inputs. If false, will disallow user inputs.
     pub enabled: bool,
 }
 
+/// The content displayed on a `Toggle`'s pressable area.
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Content<'a> {
+    /// A text label, centered on the Toggle (or placed beside it, when rendered via `switch`).
+    Text(&'a str),
+    /// An icon image, drawn via conrod's `Image` widget and centered on the Toggle.
+    ///
+    /// `on` is shown while the Toggle's value is `true`, `off` while it is `false`.
+    Image {
+        on: image::Id,
+        off: image::Id,
+    },
+}
+
 widget_style!{
     /// Styling for the Toggle including coloring, bordering and labelling.
     style Style {
@@ -43,38 +66,178 @@ widget_style!{
         - label_color: Color { theme.label_color }
         /// The font size for the Toggle's Text label.
         - label_font_size: FontSize { theme.font_size_medium }
+        /// The color of the switch's track when rendered via `switch`.
+        - track_color: Color { theme.shape_color }
+        /// The color of the switch's knob when rendered via `switch`.
+        - knob_color: Color { theme.shape_color.plain_contrast() }
+        /// The duration (in seconds) of the knob's slide animation when rendered via `switch`.
+        ///
+        /// A value of `0.0` disables the animation, snapping the knob straight to its target.
+        - transition_duration: Scalar { 0.15 }
+        /// The color of the Toggle when the mouse is hovering over it.
+        - highlighted_color: Color { theme.shape_color.highlighted() }
+        /// The color of the Toggle's border when the mouse is hovering over it.
+        - highlighted_border_color: Color { theme.border_color.highlighted() }
+        /// The color of the Toggle's label when the mouse is hovering over it.
+        - highlighted_label_color: Color { theme.label_color.highlighted() }
+        /// The color of the Toggle while it is pressed.
+        - pressed_color: Color { theme.shape_color.clicked() }
+        /// The color of the Toggle's border while it is pressed.
+        - pressed_border_color: Color { theme.border_color.clicked() }
+        /// The color of the Toggle's label while it is pressed.
+        - pressed_label_color: Color { theme.label_color.clicked() }
+        /// The color of the Toggle when `enabled` is `false`.
+        - disabled_color: Color { theme.shape_color.with_luminance(0.1) }
+        /// The color of the Toggle's border when `enabled` is `false`.
+        - disabled_border_color: Color { theme.border_color.with_luminance(0.1) }
+        /// The color of the Toggle's label when `enabled` is `false`.
+        - disabled_label_color: Color { theme.label_color.with_luminance(0.1) }
+        /// The color that the lit end of the bevelled gradient is blended halfway toward from the
+        /// Toggle's current fill color.
+        - bevel_highlight_color: Color { theme.shape_color.highlighted() }
+        /// The color that the shaded end of the bevelled gradient is blended halfway toward from
+        /// the Toggle's current fill color.
+        - bevel_shadow_color: Color { theme.shape_color.clicked() }
+        /// The color of the switch's track when `enabled` is `false`.
+        - disabled_track_color: Color { theme.shape_color.with_luminance(0.1) }
+        /// The color of the switch's knob when `enabled` is `false`.
+        - disabled_knob_color: Color { theme.shape_color.plain_contrast().with_luminance(0.1) }
+    }
+}
+
+/// The interaction state of a `Toggle`, used to select which of `Style`'s per-state colors
+/// should be drawn.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Interaction {
+    /// Neither hovered, pressed nor disabled.
+    Normal,
+    /// The mouse is hovering over the widget.
+    Highlighted,
+    /// The widget is being pressed with the left mouse button.
+    Pressed,
+    /// `enabled` is `false`, so the widget ignores all input.
+    Disabled,
+}
+
+impl Interaction {
+    fn color(self, style: &Style, theme: &Theme) -> Color {
+        match self {
+            Interaction::Normal => style.color(theme),
+            Interaction::Highlighted => style.highlighted_color(theme),
+            Interaction::Pressed => style.pressed_color(theme),
+            Interaction::Disabled => style.disabled_color(theme),
+        }
+    }
+
+    fn border_color(self, style: &Style, theme: &Theme) -> Color {
+        match self {
+            Interaction::Normal => style.border_color(theme),
+            Interaction::Highlighted => style.highlighted_border_color(theme),
+            Interaction::Pressed => style.pressed_border_color(theme),
+            Interaction::Disabled => style.disabled_border_color(theme),
+        }
+    }
+
+    fn label_color(self, style: &Style, theme: &Theme) -> Color {
+        match self {
+            Interaction::Normal => style.label_color(theme),
+            Interaction::Highlighted => style.highlighted_label_color(theme),
+            Interaction::Pressed => style.pressed_label_color(theme),
+            Interaction::Disabled => style.disabled_label_color(theme),
+        }
     }
 }
 
+/// Darken a `Color` relative to its current luminance, rather than pinning it to an absolute
+/// value - so that dimming a color that's already dim (e.g. `disabled_color`'s default) still
+/// produces a visibly different result instead of a no-op.
+fn dim(color: Color) -> Color {
+    let luminance = color.luminance();
+    color.with_luminance(luminance * 0.5)
+}
+
+/// Linearly interpolate between two `Color`s, where `t` of `0.0` yields `a` and `1.0` yields `b`.
+fn mix(a: Color, b: Color, t: f32) -> Color {
+    Color::Rgba(
+        a.red() + (b.red() - a.red()) * t,
+        a.green() + (b.green() - a.green()) * t,
+        a.blue() + (b.blue() - a.blue()) * t,
+        a.alpha() + (b.alpha() - a.alpha()) * t,
+    )
+}
+
 /// The state of the Toggle.
 #[derive(Clone, Debug, PartialEq)]
 pub struct State {
     rectangle_idx: widget::IndexSlot,
     label_idx: widget::IndexSlot,
+    image_idx: widget::IndexSlot,
+    gradient_idx: widget::IndexSlot,
+    track_idx: widget::IndexSlot,
+    knob_idx: widget::IndexSlot,
+    /// The current progress of the switch's sliding knob animation, where `0.0` is fully off
+    /// and `1.0` is fully on.
+    knob_progress: f64,
+    /// The instant at which `knob_progress` was last updated, used to advance the animation by
+    /// the elapsed time on each `update`.
+    last_update: Instant,
+}
+
+/// A discrete event produced by interacting with a `Toggle` via the left mouse button.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ToggleEvent {
+    /// The left mouse button was pressed over the `Toggle`.
+    Pressed,
+    /// The left mouse button, previously pressed over the `Toggle`, was released.
+    ///
+    /// This is yielded whether or not the cursor was still over the `Toggle` upon release - use
+    /// `Clicked` if you only care about completed clicks.
+    Released,
+    /// A full left click (a press followed by a release, both over the `Toggle`) occurred,
+    /// carrying the new boolean state that the `Toggle` would take on.
+    Clicked(bool),
 }
 
 /// The `Event` type yielded by the `Toggle` widget.
 ///
-/// Implements `Iterator` yielding a `bool` indicating the new state for each time the `Toggle` was
-/// clicked with the left mouse button since the last update.
+/// Implements `Iterator` yielding a `ToggleEvent` for every left mouse `Pressed`, `Released` and
+/// `Clicked` event that has occurred since the last update.
 #[derive(Clone, Debug)]
-#[allow(missing_copy_implementations)]
 pub struct TimesClicked {
-    state: bool,
-    count: u16,
+    events: ::std::vec::IntoIter<ToggleEvent>,
 }
 
-
 impl Iterator for TimesClicked {
+    type Item = ToggleEvent;
+    fn next(&mut self) -> Option<Self::Item> {
+        self.events.next()
+    }
+}
+
+impl TimesClicked {
+    /// Collapse the `ToggleEvent`s down to an iterator of `bool`s, each indicating the `Toggle`'s
+    /// new state for a completed left click, for backwards compatibility with code that only
+    /// cares about clicks.
+    pub fn clicks(self) -> Clicks {
+        Clicks { events: self.events }
+    }
+}
+
+/// An iterator yielding a `bool`, the `Toggle`'s new state, for every completed left click.
+#[derive(Clone, Debug)]
+pub struct Clicks {
+    events: ::std::vec::IntoIter<ToggleEvent>,
+}
+
+impl Iterator for Clicks {
     type Item = bool;
     fn next(&mut self) -> Option<Self::Item> {
-        if self.count > 0 {
-            self.count -= 1;
-            self.state = !self.state;
-            Some(self.state)
-        } else {
-            None
+        while let Some(event) = self.events.next() {
+            if let ToggleEvent::Clicked(new_value) = event {
+                return Some(new_value);
+            }
         }
+        None
     }
 }
 
@@ -85,15 +248,83 @@ impl<'a> Toggle<'a> {
     pub fn new(value: bool) -> Toggle<'a> {
         Toggle {
             common: widget::CommonBuilder::new(),
-            maybe_label: None,
+            maybe_content: None,
             value: value,
             style: Style::new(),
+            is_switch: false,
+            flat: false,
             enabled: true,
         }
     }
 
+    /// Render the Toggle's pressable area as a single flat color rather than the default
+    /// bevelled highlight/shadow gradient.
+    ///
+    /// Only affects the plain (non-`switch`) rendering - the switch's track is always drawn as
+    /// a flat fill, so `flat()` has no effect when combined with `switch()`.
+    pub fn flat(mut self) -> Self {
+        self.flat = true;
+        self
+    }
+
+    /// The color that the lit end of the bevelled gradient is blended halfway toward from the
+    /// Toggle's current fill color.
+    pub fn bevel_highlight_color(mut self, color: Color) -> Self {
+        self.style.bevel_highlight_color = Some(color);
+        self
+    }
+
+    /// The color that the shaded end of the bevelled gradient is blended halfway toward from the
+    /// Toggle's current fill color.
+    pub fn bevel_shadow_color(mut self, color: Color) -> Self {
+        self.style.bevel_shadow_color = Some(color);
+        self
+    }
+
+    /// Display the given image on the Toggle's pressable area, regardless of its value.
+    pub fn image(mut self, id: image::Id) -> Self {
+        self.maybe_content = Some(Content::Image { on: id, off: id });
+        self
+    }
+
+    /// Display `on` while the Toggle's value is `true` and `off` while it is `false`.
+    pub fn images(mut self, on: image::Id, off: image::Id) -> Self {
+        self.maybe_content = Some(Content::Image { on: on, off: off });
+        self
+    }
+
+    /// Render the `Toggle` as a sliding switch - a track with a circular knob that slides to the
+    /// left half when `false` and the right half when `true` - rather than as a plain bordered
+    /// rectangle. The track is drawn as a `BorderedRectangle`, so its corners are not rounded.
+    ///
+    /// The switch's track is always drawn as a flat fill, so `flat()` has no effect when
+    /// combined with `switch()`.
+    pub fn switch(mut self) -> Self {
+        self.is_switch = true;
+        self
+    }
+
+    /// The duration of the switch knob's slide animation in seconds.
+    ///
+    /// Pass `0.0` to disable the animation entirely.
+    pub fn transition_duration(mut self, secs: Scalar) -> Self {
+        self.style.transition_duration = Some(secs);
+        self
+    }
+
     builder_methods!{
         pub enabled { enabled = bool }
+        pub hover_color { style.highlighted_color = Some(Color) }
+        pub hover_border_color { style.highlighted_border_color = Some(Color) }
+        pub hover_label_color { style.highlighted_label_color = Some(Color) }
+        pub pressed_color { style.pressed_color = Some(Color) }
+        pub pressed_border_color { style.pressed_border_color = Some(Color) }
+        pub pressed_label_color { style.pressed_label_color = Some(Color) }
+        pub disabled_color { style.disabled_color = Some(Color) }
+        pub disabled_border_color { style.disabled_border_color = Some(Color) }
+        pub disabled_label_color { style.disabled_label_color = Some(Color) }
+        pub disabled_track_color { style.disabled_track_color = Some(Color) }
+        pub disabled_knob_color { style.disabled_knob_color = Some(Color) }
     }
 
 }
@@ -115,6 +346,12 @@ impl<'a> Widget for Toggle<'a> {
         State {
             rectangle_idx: widget::IndexSlot::new(),
             label_idx: widget::IndexSlot::new(),
+            image_idx: widget::IndexSlot::new(),
+            gradient_idx: widget::IndexSlot::new(),
+            track_idx: widget::IndexSlot::new(),
+            knob_idx: widget::IndexSlot::new(),
+            knob_progress: if self.value { 1.0 } else { 0.0 },
+            last_update: Instant::now(),
         }
     }
 
@@ -125,51 +362,220 @@ impl<'a> Widget for Toggle<'a> {
     /// Update the state of the Toggle.
     fn update(self, args: widget::UpdateArgs<Self>) -> Self::Event {
         let widget::UpdateArgs { idx, state, style, rect, mut ui, .. } = args;
-        let Toggle { value, enabled, maybe_label, .. } = self;
+        let Toggle { value, enabled, maybe_content, is_switch, flat, .. } = self;
 
-        let times_clicked = TimesClicked {
-            state: value,
-            count: if enabled { ui.widget_input(idx).clicks().left().count() as u16 } else { 0 },
-        };
+        let mut new_value = value;
+        let mut events = Vec::new();
+        if enabled {
+            // Walk the widget's input events in order so that `Pressed` and `Released` reflect
+            // the actual press/release sequence, rather than just the net result of a click.
+            for widget_event in ui.widget_input(idx).events() {
+                match widget_event {
+                    event::Widget::Press(press) => {
+                        if let event::Button::Mouse(input::MouseButton::Left, _) = press.button {
+                            events.push(ToggleEvent::Pressed);
+                        }
+                    },
+                    event::Widget::Release(release) => {
+                        if let event::Button::Mouse(input::MouseButton::Left, _) = release.button {
+                            events.push(ToggleEvent::Released);
+                        }
+                    },
+                    event::Widget::Click(click) => {
+                        if let event::Button::Mouse(input::MouseButton::Left, _) = click.button {
+                            new_value = !new_value;
+                            events.push(ToggleEvent::Clicked(new_value));
+                        }
+                    },
+                    _ => (),
+                }
+            }
+        }
 
-        // BorderedRectangle widget.
-        let rectangle_idx = state.rectangle_idx.get(&mut ui);
-        let dim = rect.dim();
-        let border = style.border(ui.theme());
-        let color = {
-            let color = style.color(ui.theme());
-            let new_value = times_clicked.clone().last().unwrap_or(value);
-            let color = if new_value { color } else { color.with_luminance(0.1) };
+        let interaction = if !enabled {
+            Interaction::Disabled
+        } else {
             match ui.widget_input(idx).mouse() {
                 Some(mouse) =>
-                    if mouse.buttons.left().is_down() { color.clicked() }
-                    else { color.highlighted() },
-                None => color,
+                    if mouse.buttons.left().is_down() { Interaction::Pressed }
+                    else { Interaction::Highlighted },
+                None => Interaction::Normal,
             }
         };
-        let border_color = style.border_color(ui.theme());
-        widget::BorderedRectangle::new(dim)
-            .middle_of(idx)
-            .graphics_for(idx)
-            .color(color)
-            .border(border)
-            .border_color(border_color)
-            .set(rectangle_idx, &mut ui);
-
-        // Label widget.
-        if let Some(label) = maybe_label {
-            let label_idx = state.label_idx.get(&mut ui);
-            let color = style.label_color(ui.theme());
-            let font_size = style.label_font_size(ui.theme());
-            widget::Text::new(label)
-                .middle_of(rectangle_idx)
+
+        if is_switch {
+            let transition_secs = style.transition_duration(ui.theme());
+            let now = Instant::now();
+            let target = if new_value { 1.0 } else { 0.0 };
+            let progress = if transition_secs <= 0.0 {
+                target
+            } else {
+                let elapsed = now.duration_since(state.last_update);
+                let dt = elapsed.as_secs() as f64 + elapsed.subsec_nanos() as f64 / 1.0e9;
+                let step = dt / transition_secs;
+                if target > state.knob_progress {
+                    (state.knob_progress + step).min(target)
+                } else {
+                    (state.knob_progress - step).max(target)
+                }
+            };
+
+            // Always refresh `last_update`, even once the knob has settled at rest - otherwise
+            // the next click computes `elapsed` against a stale timestamp and the knob snaps
+            // straight to its target instead of sliding.
+            state.update(|state| {
+                state.knob_progress = progress;
+                state.last_update = now;
+            });
+
+            if progress > 0.0 && progress < 1.0 {
+                ui.needs_redraw();
+            }
+
+            let dim = rect.dim();
+            let border = style.border(ui.theme());
+            let border_color = interaction.border_color(&style, ui.theme());
+            // Derive each interaction's track fill from the user's own `track_color` rather than
+            // falling back to the flat-rectangle's generic per-state colors, so a custom track
+            // hue is still tinted (not replaced) on hover/press. `Disabled` uses its own
+            // `disabled_track_color` style field rather than the flat rendering's `disabled_color`.
+            let track_color = match interaction {
+                Interaction::Normal => style.track_color(ui.theme()),
+                Interaction::Highlighted => style.track_color(ui.theme()).highlighted(),
+                Interaction::Pressed => style.track_color(ui.theme()).clicked(),
+                Interaction::Disabled => style.disabled_track_color(ui.theme()),
+            };
+            let knob_color = if let Interaction::Disabled = interaction {
+                style.disabled_knob_color(ui.theme())
+            } else {
+                style.knob_color(ui.theme())
+            };
+
+            let track_idx = state.track_idx.get(&mut ui);
+            widget::BorderedRectangle::new(dim)
+                .middle_of(idx)
+                .graphics_for(idx)
+                .color(track_color)
+                .border(border)
+                .border_color(border_color)
+                .set(track_idx, &mut ui);
+
+            let knob_diameter = dim.1 - border * 2.0;
+            let knob_travel = (dim.0 - dim.1) / 2.0;
+            let knob_x = utils::map_range(progress, 0.0, 1.0, -knob_travel, knob_travel);
+            let knob_idx = state.knob_idx.get(&mut ui);
+            widget::Oval::fill([knob_diameter, knob_diameter])
+                .color(knob_color)
+                .x_y_relative_to(track_idx, knob_x, 0.0)
+                .graphics_for(idx)
+                .set(knob_idx, &mut ui);
+
+            match maybe_content {
+                Some(Content::Text(label)) => {
+                    let label_idx = state.label_idx.get(&mut ui);
+                    let color = interaction.label_color(&style, ui.theme());
+                    let font_size = style.label_font_size(ui.theme());
+                    widget::Text::new(label)
+                        .left_from(track_idx, font_size as Scalar / 2.0)
+                        .graphics_for(idx)
+                        .color(color)
+                        .font_size(font_size)
+                        .set(label_idx, &mut ui);
+                }
+                Some(Content::Image { on, off }) => {
+                    let image_idx = state.image_idx.get(&mut ui);
+                    let image_id = if new_value { on } else { off };
+                    widget::Image::new(image_id)
+                        .middle_of(track_idx)
+                        .graphics_for(idx)
+                        .set(image_idx, &mut ui);
+                }
+                None => (),
+            }
+        } else {
+            // BorderedRectangle widget.
+            let rectangle_idx = state.rectangle_idx.get(&mut ui);
+            let dim = rect.dim();
+            let border = style.border(ui.theme());
+            // Carry the on/off dimming through for every interaction, including `Disabled`, so a
+            // disabled Toggle's value is still legible rather than looking identical either way.
+            // The dimming is applied relative to the resolved color's own luminance (rather than
+            // pinning it to an absolute value) so it still has an effect on states - like
+            // `disabled_color`'s default - whose luminance is already low.
+            let color = {
+                let color = interaction.color(&style, ui.theme());
+                if new_value { color } else { dim(color) }
+            };
+            let border_color = interaction.border_color(&style, ui.theme());
+            widget::BorderedRectangle::new(dim)
+                .middle_of(idx)
                 .graphics_for(idx)
                 .color(color)
-                .font_size(font_size)
-                .set(label_idx, &mut ui);
+                .border(border)
+                .border_color(border_color)
+                .set(rectangle_idx, &mut ui);
+
+            // Bevelled gradient fill, composited from two triangles spanning the rectangle's
+            // vertical extent between `bevel_highlight_color` and `bevel_shadow_color`, each
+            // blended with the active `Interaction`'s `color` so the per-state palette (and the
+            // on/off dimming above) still reads through the gradient instead of being painted
+            // over. The shading flips while the Toggle is pressed, so the surface reads as
+            // pushed in.
+            if !flat {
+                let bevel_highlight_color = style.bevel_highlight_color(ui.theme());
+                let bevel_shadow_color = style.bevel_shadow_color(ui.theme());
+                let lit = mix(color, bevel_highlight_color, 0.5);
+                let shaded = mix(color, bevel_shadow_color, 0.5);
+                let (top_color, bottom_color) = match interaction {
+                    Interaction::Pressed => (shaded, lit),
+                    _ => (lit, shaded),
+                };
+
+                let l = rect.left() + border;
+                let r = rect.right() - border;
+                let t = rect.top() - border;
+                let b = rect.bottom() + border;
+                let top_left = ([l, t], top_color);
+                let top_right = ([r, t], top_color);
+                let bottom_left = ([l, b], bottom_color);
+                let bottom_right = ([r, b], bottom_color);
+                let triangles = [
+                    Triangle([top_left, top_right, bottom_left]),
+                    Triangle([top_right, bottom_right, bottom_left]),
+                ];
+
+                let gradient_idx = state.gradient_idx.get(&mut ui);
+                widget::Triangles::multi_color(triangles.iter().cloned())
+                    .graphics_for(idx)
+                    .set(gradient_idx, &mut ui);
+            }
+
+            // Content widget.
+            match maybe_content {
+                Some(Content::Text(label)) => {
+                    let label_idx = state.label_idx.get(&mut ui);
+                    let color = interaction.label_color(&style, ui.theme());
+                    let font_size = style.label_font_size(ui.theme());
+                    widget::Text::new(label)
+                        .middle_of(rectangle_idx)
+                        .graphics_for(idx)
+                        .color(color)
+                        .font_size(font_size)
+                        .set(label_idx, &mut ui);
+                }
+                Some(Content::Image { on, off }) => {
+                    let image_idx = state.image_idx.get(&mut ui);
+                    let image_id = if new_value { on } else { off };
+                    widget::Image::new(image_id)
+                        .middle_of(rectangle_idx)
+                        .graphics_for(idx)
+                        .set(image_idx, &mut ui);
+                }
+                None => (),
+            }
         }
 
-        times_clicked
+        TimesClicked { events: events.into_iter() }
     }
 }
 
@@ -186,8 +592,12 @@ impl<'a> Borderable for Toggle<'a> {
 }
 
 impl<'a> Labelable<'a> for Toggle<'a> {
+    fn label(mut self, text: &'a str) -> Self {
+        self.maybe_content = Some(Content::Text(text));
+        self
+    }
+
     builder_methods!{
-        label { maybe_label = Some(&'a str) }
         label_color { style.label_color = Some(Color) }
         label_font_size { style.label_font_size = Some(FontSize) }
     }